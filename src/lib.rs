@@ -1,26 +1,129 @@
 //! # fmtsize
 //!
 //! `fmtsize` provides human-readable formatting for things like file sizes. It
-//! attempts to find the largest shorthand size possible for a given value,
-//! although it's limited to "gigabytes." Someday we may upgrade to terabytes. :)
-//! 
+//! attempts to find the largest shorthand size possible for a given value, from
+//! plain bytes all the way up through exabytes.
+//!
 //! ```
 //! # use fmtsize::{Conventional, FmtSize};
 //! println!("{}", 492_752_310_u64.fmt_size(Conventional)); // 469.93 MB
 //! ```
+//!
+//! The `std` feature is on by default. With it disabled, the crate is
+//! `no_std`: [`ByteSizeFormatter`]'s `Display` impl never allocates, and
+//! [`format_into`] lets you render straight into a fixed buffer. The parsing
+//! subsystem ([`parse_size`], [`ParsedSize`]) needs an allocator and is only
+//! available with `std` enabled.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::fmt::{self, Display};
+use core::ops;
+
+#[cfg(feature = "std")]
+mod parse;
+
+#[cfg(feature = "std")]
+pub use parse::{parse_size, ParseError, ParsedSize, UnitBase};
 
-use std::fmt::{self, Display};
+/// A single rung on a unit ladder: the smallest size (in bytes) at which this
+/// unit applies, the divisor used to express a size in that unit, and the
+/// unit's display name.
+type UnitEntry = (u64, u64, &'static str);
+
+/// Selects the largest unit in `table` whose threshold is `<= size`, falling
+/// back to a raw "B" unit for anything smaller than the first entry.
+///
+/// `table` is expected to be sorted in ascending order by threshold.
+fn select_unit(table: &[UnitEntry], size: u64) -> (u64, &'static str) {
+    table
+        .iter()
+        .rev()
+        .find(|&&(threshold, _, _)| size >= threshold)
+        .map(|&(_, divisor, name)| (divisor, name))
+        .unwrap_or((1, "B"))
+}
 
 mod conventional {
+    use super::UnitEntry;
+
     pub const KILOBYTE: u64 = 1 << 10;
     pub const MEGABYTE: u64 = 1 << 20;
     pub const GIGABYTE: u64 = 1 << 30;
+    pub const TERABYTE: u64 = 1 << 40;
+    pub const PETABYTE: u64 = 1 << 50;
+    pub const EXABYTE: u64 = 1 << 60;
+
+    pub const UNITS: [UnitEntry; 6] = [
+        (KILOBYTE, KILOBYTE, "KB"),
+        (MEGABYTE, MEGABYTE, "MB"),
+        (GIGABYTE, GIGABYTE, "GB"),
+        (TERABYTE, TERABYTE, "TB"),
+        (PETABYTE, PETABYTE, "PB"),
+        (EXABYTE, EXABYTE, "EB"),
+    ];
 }
 
 mod decimal {
+    use super::UnitEntry;
+
     pub const KILOBYTE: u64 = 1000;
     pub const MEGABYTE: u64 = 1_000_000;
     pub const GIGABYTE: u64 = 1_000_000_000;
+    pub const TERABYTE: u64 = 1_000_000_000_000;
+    pub const PETABYTE: u64 = 1_000_000_000_000_000;
+    pub const EXABYTE: u64 = 1_000_000_000_000_000_000;
+
+    pub const UNITS: [UnitEntry; 6] = [
+        (KILOBYTE, KILOBYTE, "KB"),
+        (MEGABYTE, MEGABYTE, "MB"),
+        (GIGABYTE, GIGABYTE, "GB"),
+        (TERABYTE, TERABYTE, "TB"),
+        (PETABYTE, PETABYTE, "PB"),
+        (EXABYTE, EXABYTE, "EB"),
+    ];
+}
+
+mod binary {
+    use super::conventional::{EXABYTE, GIGABYTE, KILOBYTE, MEGABYTE, PETABYTE, TERABYTE};
+    use super::UnitEntry;
+
+    pub const UNITS: [UnitEntry; 6] = [
+        (KILOBYTE, KILOBYTE, "KiB"),
+        (MEGABYTE, MEGABYTE, "MiB"),
+        (GIGABYTE, GIGABYTE, "GiB"),
+        (TERABYTE, TERABYTE, "TiB"),
+        (PETABYTE, PETABYTE, "PiB"),
+        (EXABYTE, EXABYTE, "EiB"),
+    ];
+}
+
+mod decimal_bits {
+    use super::decimal::{EXABYTE, GIGABYTE, KILOBYTE, MEGABYTE, PETABYTE, TERABYTE};
+    use super::UnitEntry;
+
+    pub const UNITS: [UnitEntry; 6] = [
+        (KILOBYTE, KILOBYTE, "Kb"),
+        (MEGABYTE, MEGABYTE, "Mb"),
+        (GIGABYTE, GIGABYTE, "Gb"),
+        (TERABYTE, TERABYTE, "Tb"),
+        (PETABYTE, PETABYTE, "Pb"),
+        (EXABYTE, EXABYTE, "Eb"),
+    ];
+}
+
+mod binary_bits {
+    use super::conventional::{EXABYTE, GIGABYTE, KILOBYTE, MEGABYTE, PETABYTE, TERABYTE};
+    use super::UnitEntry;
+
+    pub const UNITS: [UnitEntry; 6] = [
+        (KILOBYTE, KILOBYTE, "Kib"),
+        (MEGABYTE, MEGABYTE, "Mib"),
+        (GIGABYTE, GIGABYTE, "Gib"),
+        (TERABYTE, TERABYTE, "Tib"),
+        (PETABYTE, PETABYTE, "Pib"),
+        (EXABYTE, EXABYTE, "Eib"),
+    ];
 }
 
 /// Used to format values in accordance with
@@ -37,6 +140,15 @@ pub trait Format {
     /// For instance, something larger than a single megabyte and smaller than
     /// one gigabyte will be called "megabytes."
     fn name(&self, size: u64) -> &'static str;
+
+    /// Whether this format reports bits rather than bytes.
+    ///
+    /// When `true`, [`ByteSizeFormatter`] multiplies the byte count by 8
+    /// before selecting a unit, so a bit-oriented `Format` can be handed a
+    /// plain byte count the same way a byte-oriented one is.
+    fn is_bits(&self) -> bool {
+        false
+    }
 }
 
 /// Old-school formatting: a megabyte is 1024 kilobytes, dammit!
@@ -45,21 +157,11 @@ pub struct Conventional;
 
 impl Format for Conventional {
     fn divisor(&self, size: u64) -> u64 {
-        use conventional::*;
-        match size {
-            size if size < MEGABYTE => KILOBYTE,
-            size if size < GIGABYTE => MEGABYTE,
-            _ => GIGABYTE,
-        }
+        select_unit(&conventional::UNITS, size).0
     }
 
     fn name(&self, size: u64) -> &'static str {
-        use conventional::*;
-        match size {
-            size if size < MEGABYTE => "KB",
-            size if size < GIGABYTE => "MB",
-            _ => "GB",
-        }
+        select_unit(&conventional::UNITS, size).1
     }
 }
 
@@ -70,21 +172,150 @@ pub struct Decimal;
 
 impl Format for Decimal {
     fn divisor(&self, size: u64) -> u64 {
-        use decimal::*;
-        match size {
-            size if size < MEGABYTE => KILOBYTE,
-            size if size < GIGABYTE => MEGABYTE,
-            _ => GIGABYTE,
-        }
+        select_unit(&decimal::UNITS, size).0
     }
 
     fn name(&self, size: u64) -> &'static str {
-        use conventional::*;
-        match size {
-            size if size < MEGABYTE => "KB",
-            size if size < GIGABYTE => "MB",
-            _ => "GB",
+        select_unit(&decimal::UNITS, size).1
+    }
+}
+
+/// IEC formatting: a megabyte is 1024 kilobytes, but we call it a "mebibyte"
+/// and spell it "MiB" so nobody can accuse us of lying.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Binary;
+
+impl Format for Binary {
+    fn divisor(&self, size: u64) -> u64 {
+        select_unit(&binary::UNITS, size).0
+    }
+
+    fn name(&self, size: u64) -> &'static str {
+        select_unit(&binary::UNITS, size).1
+    }
+}
+
+/// Windows Explorer-style formatting: binary magnitudes (a megabyte is 1024
+/// kilobytes) reported under SI-style labels ("MB" rather than "MiB"). This
+/// is exactly [`Conventional`]'s table, kept as a distinct type so callers
+/// can name the convention they mean; it delegates rather than duplicating
+/// the unit table so the two can't silently drift apart.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Windows;
+
+impl Format for Windows {
+    fn divisor(&self, size: u64) -> u64 {
+        Conventional.divisor(size)
+    }
+
+    fn name(&self, size: u64) -> &'static str {
+        Conventional.name(size)
+    }
+}
+
+/// Bit-oriented formatting for bandwidth and throughput: decimal (1000-based)
+/// magnitudes reported in bits (`Kb`, `Mb`, `Gb`, ...) rather than bytes.
+/// [`ByteSizeFormatter`] multiplies the incoming byte count by 8 before
+/// selecting a unit, so callers don't need to do that conversion themselves.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DecimalBits;
+
+impl Format for DecimalBits {
+    fn divisor(&self, size: u64) -> u64 {
+        select_unit(&decimal_bits::UNITS, size).0
+    }
+
+    fn name(&self, size: u64) -> &'static str {
+        select_unit(&decimal_bits::UNITS, size).1
+    }
+
+    fn is_bits(&self) -> bool {
+        true
+    }
+}
+
+/// Bit-oriented formatting for bandwidth and throughput: binary (1024-based)
+/// magnitudes reported under IEC-style bit labels (`Kib`, `Mib`, `Gib`, ...).
+/// See [`DecimalBits`] for the byte-to-bit conversion this performs
+/// automatically.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BinaryBits;
+
+impl Format for BinaryBits {
+    fn divisor(&self, size: u64) -> u64 {
+        select_unit(&binary_bits::UNITS, size).0
+    }
+
+    fn name(&self, size: u64) -> &'static str {
+        select_unit(&binary_bits::UNITS, size).1
+    }
+
+    fn is_bits(&self) -> bool {
+        true
+    }
+}
+
+/// Renders `size` bytes according to `fmt` with `precision` fractional
+/// digits, writing straight into `writer` with no heap allocation. This is
+/// what `no_std` callers reach for in place of [`ByteSizeFormatter`]'s
+/// `Display` impl, which (when the `std` feature is enabled) is free to
+/// grow a `String` instead and so has no precision limit.
+pub fn format_into<F: Format>(
+    writer: &mut impl fmt::Write,
+    size: u64,
+    fmt: &F,
+    precision: usize,
+) -> fmt::Result {
+    let size = if fmt.is_bits() {
+        size.saturating_mul(8)
+    } else {
+        size
+    };
+
+    let divisor = fmt.divisor(size) as f32;
+    let value = size as f32 / divisor;
+    write!(writer, "{:.*} {}", precision, value, fmt.name(size))
+}
+
+/// A fixed-capacity, non-allocating buffer for composing the rendered size
+/// before it's padded to the requested field width. Only used on the
+/// `no_std` (`std` feature disabled) path, where there's no allocator to
+/// fall back on; a rendering that doesn't fit (e.g. a pathologically large
+/// requested precision) reports `Err(fmt::Error)` rather than panicking,
+/// since `no_std` callers can't reach the `std::fmt` glue that `.expect()`s
+/// a successful `Display` impl.
+#[cfg(not(feature = "std"))]
+struct StackBuf<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+#[cfg(not(feature = "std"))]
+impl<const N: usize> StackBuf<N> {
+    fn new() -> Self {
+        StackBuf {
+            bytes: [0; N],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or("")
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<const N: usize> fmt::Write for StackBuf<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let bytes = s.as_bytes();
+        let end = self.len + bytes.len();
+        if end > N {
+            return Err(fmt::Error);
         }
+
+        self.bytes[self.len..end].copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
     }
 }
 
@@ -96,9 +327,54 @@ pub struct ByteSizeFormatter<F = Conventional> {
 
 impl<F: Format> Display for ByteSizeFormatter<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let divisor = self.fmt.divisor(self.size) as f32;
-        let size = self.size as f32 / divisor;
-        write!(f, "{:.2} {}", size, self.fmt.name(self.size))
+        let precision = f.precision().unwrap_or(2);
+
+        #[cfg(feature = "std")]
+        {
+            let mut rendered = std::string::String::new();
+            format_into(&mut rendered, self.size, &self.fmt, precision)?;
+            pad_rendered(f, &rendered)
+        }
+
+        #[cfg(not(feature = "std"))]
+        {
+            let mut buf = StackBuf::<64>::new();
+            format_into(&mut buf, self.size, &self.fmt, precision)?;
+            pad_rendered(f, buf.as_str())
+        }
+    }
+}
+
+/// Writes `rendered` into `f`, honoring the formatter's requested width,
+/// fill and alignment the way a normal `Display` value would.
+fn pad_rendered(f: &mut fmt::Formatter<'_>, rendered: &str) -> fmt::Result {
+    let Some(width) = f.width() else {
+        return f.write_str(rendered);
+    };
+
+    let len = rendered.chars().count();
+    if width <= len {
+        return f.write_str(rendered);
+    }
+
+    let pad = width - len;
+    let fill = f.fill();
+    match f.align().unwrap_or(fmt::Alignment::Left) {
+        fmt::Alignment::Left => {
+            write!(f, "{}", rendered)?;
+            (0..pad).try_for_each(|_| write!(f, "{}", fill))
+        }
+        fmt::Alignment::Right => {
+            (0..pad).try_for_each(|_| write!(f, "{}", fill))?;
+            write!(f, "{}", rendered)
+        }
+        fmt::Alignment::Center => {
+            let left = pad / 2;
+            let right = pad - left;
+            (0..left).try_for_each(|_| write!(f, "{}", fill))?;
+            write!(f, "{}", rendered)?;
+            (0..right).try_for_each(|_| write!(f, "{}", fill))
+        }
     }
 }
 
@@ -115,9 +391,133 @@ impl FmtSize for u64 {
     }
 }
 
+/// An owned size in bytes.
+///
+/// Where [`FmtSize::fmt_size`] lazily formats a bare `u64`, `ByteSize` is a
+/// first-class value you can build from a convenient unit, combine with
+/// other sizes via arithmetic, and render with whichever [`Format`] you
+/// like. Arithmetic saturates rather than overflowing, since a size can
+/// never sensibly go negative or past `u64::MAX`.
+///
+/// ```
+/// # use fmtsize::{ByteSize, Conventional, FmtSize};
+/// let size = ByteSize::gb(4) + ByteSize::mb(512);
+/// println!("{}", size.fmt_size(Conventional));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(u64);
+
+impl ByteSize {
+    /// Constructs a `ByteSize` from a raw byte count.
+    pub fn b(bytes: u64) -> Self {
+        ByteSize(bytes)
+    }
+
+    /// Constructs a `ByteSize` from a count of decimal (1000-based) kilobytes.
+    pub fn kb(n: u64) -> Self {
+        ByteSize(n.saturating_mul(decimal::KILOBYTE))
+    }
+
+    /// Constructs a `ByteSize` from a count of decimal (1000-based) megabytes.
+    pub fn mb(n: u64) -> Self {
+        ByteSize(n.saturating_mul(decimal::MEGABYTE))
+    }
+
+    /// Constructs a `ByteSize` from a count of decimal (1000-based) gigabytes.
+    pub fn gb(n: u64) -> Self {
+        ByteSize(n.saturating_mul(decimal::GIGABYTE))
+    }
+
+    /// Constructs a `ByteSize` from a count of decimal (1000-based) terabytes.
+    pub fn tb(n: u64) -> Self {
+        ByteSize(n.saturating_mul(decimal::TERABYTE))
+    }
+
+    /// Constructs a `ByteSize` from a count of decimal (1000-based) petabytes.
+    pub fn pb(n: u64) -> Self {
+        ByteSize(n.saturating_mul(decimal::PETABYTE))
+    }
+
+    /// Constructs a `ByteSize` from a count of decimal (1000-based) exabytes.
+    pub fn eb(n: u64) -> Self {
+        ByteSize(n.saturating_mul(decimal::EXABYTE))
+    }
+
+    /// Constructs a `ByteSize` from a count of binary (1024-based) kibibytes.
+    pub fn kib(n: u64) -> Self {
+        ByteSize(n.saturating_mul(conventional::KILOBYTE))
+    }
+
+    /// Constructs a `ByteSize` from a count of binary (1024-based) mebibytes.
+    pub fn mib(n: u64) -> Self {
+        ByteSize(n.saturating_mul(conventional::MEGABYTE))
+    }
+
+    /// Constructs a `ByteSize` from a count of binary (1024-based) gibibytes.
+    pub fn gib(n: u64) -> Self {
+        ByteSize(n.saturating_mul(conventional::GIGABYTE))
+    }
+
+    /// Constructs a `ByteSize` from a count of binary (1024-based) tebibytes.
+    pub fn tib(n: u64) -> Self {
+        ByteSize(n.saturating_mul(conventional::TERABYTE))
+    }
+
+    /// Constructs a `ByteSize` from a count of binary (1024-based) pebibytes.
+    pub fn pib(n: u64) -> Self {
+        ByteSize(n.saturating_mul(conventional::PETABYTE))
+    }
+
+    /// Constructs a `ByteSize` from a count of binary (1024-based) exbibytes.
+    pub fn eib(n: u64) -> Self {
+        ByteSize(n.saturating_mul(conventional::EXABYTE))
+    }
+
+    /// Returns the size as a raw byte count.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl ops::Add for ByteSize {
+    type Output = ByteSize;
+
+    fn add(self, rhs: ByteSize) -> ByteSize {
+        ByteSize(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl ops::Sub for ByteSize {
+    type Output = ByteSize;
+
+    fn sub(self, rhs: ByteSize) -> ByteSize {
+        ByteSize(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl ops::Mul<u64> for ByteSize {
+    type Output = ByteSize;
+
+    fn mul(self, rhs: u64) -> ByteSize {
+        ByteSize(self.0.saturating_mul(rhs))
+    }
+}
+
+impl Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0.fmt_size(Conventional), f)
+    }
+}
+
+impl FmtSize for ByteSize {
+    fn fmt_size<F: Format>(self, fmt: F) -> ByteSizeFormatter<F> {
+        self.0.fmt_size(fmt)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Conventional, FmtSize};
+    use super::{Binary, ByteSize, Conventional, Decimal, DecimalBits, FmtSize, Windows};
 
     #[test]
     fn it_works() {
@@ -125,4 +525,65 @@ mod tests {
         let actual = 1_048_576.fmt_size(Conventional).to_string();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn binary_uses_iec_names() {
+        let expected = "1.00 MiB";
+        let actual = 1_048_576.fmt_size(Binary).to_string();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn windows_matches_conventional() {
+        let expected = "1.00 MB";
+        let actual = 1_048_576.fmt_size(Windows).to_string();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn byte_size_arithmetic_saturates() {
+        assert_eq!(ByteSize::b(u64::MAX), ByteSize::b(u64::MAX) + ByteSize::b(1));
+        assert_eq!(ByteSize::b(0), ByteSize::b(0) - ByteSize::b(1));
+    }
+
+    #[test]
+    fn byte_size_combines_units() {
+        let size = ByteSize::gb(4) + ByteSize::mb(512);
+        assert_eq!(4_000_000_000 + 512_000_000, size.as_u64());
+    }
+
+    #[test]
+    fn bits_mode_converts_bytes_to_bits() {
+        let expected = "492.75 Mb";
+        let actual = 61_594_000_u64.fmt_size(DecimalBits).to_string();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn honors_requested_precision() {
+        let expected = "470 MB";
+        let actual = format!("{:.0}", 492_752_310_u64.fmt_size(Conventional));
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn honors_width_and_alignment() {
+        let expected = "     1.00 MB";
+        let actual = format!("{:>12}", 1_048_576.fmt_size(Conventional));
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn decimal_uses_its_own_thresholds_and_names() {
+        let expected = "1.00 MB";
+        let actual = 1_000_000_u64.fmt_size(Decimal).to_string();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn conventional_reaches_terabytes_without_overflowing_gb() {
+        let expected = "4.55 TB";
+        let actual = 5_000_000_000_000_u64.fmt_size(Conventional).to_string();
+        assert_eq!(expected, actual);
+    }
 }
@@ -0,0 +1,195 @@
+//! Parsing human-readable sizes (the counterpart to [`crate::FmtSize`]).
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Which magnitude convention to assume for an ambiguous suffix like `"KB"`
+/// (as opposed to the unambiguous `"KiB"`, which is always binary).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UnitBase {
+    /// Bare suffixes are powers of 1024 (`KB` behaves like `KiB`).
+    Binary,
+    /// Bare suffixes are powers of 1000 (`KB` is 1000 bytes).
+    Decimal,
+}
+
+/// An error produced while parsing a human-readable size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input was empty (or all whitespace).
+    Empty,
+    /// The numeric portion of the input could not be parsed.
+    InvalidNumber(String),
+    /// The unit suffix was not recognized.
+    UnknownUnit(String),
+    /// The resulting size does not fit in a `u64`.
+    Overflow,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "cannot parse a size from an empty string"),
+            ParseError::InvalidNumber(s) => write!(f, "'{}' is not a valid number", s),
+            ParseError::UnknownUnit(s) => write!(f, "'{}' is not a recognized unit", s),
+            ParseError::Overflow => write!(f, "size overflows u64"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a human-readable size such as `"2.5 GB"` or `"512KiB"` into a byte
+/// count.
+///
+/// Suffixes ending in `iB` (`KiB`, `MiB`, ...) are always powers of 1024.
+/// Bare suffixes (`K`, `KB`, `M`, `MB`, ...) are interpreted according to
+/// `default_base`. Parsing is case-insensitive and tolerates optional
+/// whitespace between the number and the unit; a missing suffix is treated
+/// as a plain byte count.
+pub fn parse_size(input: &str, default_base: UnitBase) -> Result<u64, ParseError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let split_at = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split_at);
+    if number.is_empty() {
+        return Err(ParseError::InvalidNumber(input.to_string()));
+    }
+
+    let number: f64 = number
+        .parse()
+        .map_err(|_| ParseError::InvalidNumber(number.to_string()))?;
+
+    let multiplier = unit_multiplier(unit.trim(), default_base)
+        .ok_or_else(|| ParseError::UnknownUnit(unit.trim().to_string()))?;
+
+    let bytes = number * multiplier as f64;
+    let rounded = bytes.round();
+    if !rounded.is_finite() || rounded < 0.0 {
+        return Err(ParseError::Overflow);
+    }
+
+    // `u64::MAX as f64` rounds up to exactly `2^64`, so comparing `rounded`
+    // against it as a float would let `u64::MAX < rounded <= 2^64` slip
+    // through. Widen to `u128`, which represents every integer in that
+    // range exactly, and compare there instead.
+    let rounded = rounded as u128;
+    if rounded > u64::MAX as u128 {
+        return Err(ParseError::Overflow);
+    }
+
+    Ok(rounded as u64)
+}
+
+/// Resolves a unit suffix to its multiplier in bytes, or `None` if the
+/// suffix isn't recognized.
+fn unit_multiplier(unit: &str, default_base: UnitBase) -> Option<u64> {
+    if unit.is_empty() {
+        return Some(1);
+    }
+
+    let upper = unit.to_ascii_uppercase();
+    let (exponent, binary) = match upper.as_str() {
+        "B" => (0, true),
+        "K" | "KB" => (1, default_base == UnitBase::Binary),
+        "KIB" => (1, true),
+        "M" | "MB" => (2, default_base == UnitBase::Binary),
+        "MIB" => (2, true),
+        "G" | "GB" => (3, default_base == UnitBase::Binary),
+        "GIB" => (3, true),
+        "T" | "TB" => (4, default_base == UnitBase::Binary),
+        "TIB" => (4, true),
+        "P" | "PB" => (5, default_base == UnitBase::Binary),
+        "PIB" => (5, true),
+        "E" | "EB" => (6, default_base == UnitBase::Binary),
+        "EIB" => (6, true),
+        _ => return None,
+    };
+
+    let base: u64 = if binary { 1024 } else { 1000 };
+    (0..exponent).try_fold(1u64, |acc, _| acc.checked_mul(base))
+}
+
+/// A wrapper around `u64` that parses human-readable sizes via `FromStr`,
+/// using [`UnitBase::Decimal`] for bare unit suffixes (matching the common
+/// convention for CLI flags and config files, e.g. `--max-size 2.5GB`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ParsedSize(pub u64);
+
+impl FromStr for ParsedSize {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_size(s, UnitBase::Decimal).map(ParsedSize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bare_bytes() {
+        assert_eq!(Ok(512), parse_size("512", UnitBase::Decimal));
+    }
+
+    #[test]
+    fn parses_decimal_suffix() {
+        assert_eq!(Ok(2_500_000_000), parse_size("2.5GB", UnitBase::Decimal));
+    }
+
+    #[test]
+    fn parses_binary_suffix_regardless_of_default() {
+        assert_eq!(Ok(1_048_576), parse_size("1 MiB", UnitBase::Decimal));
+    }
+
+    #[test]
+    fn treats_bare_suffix_as_binary_when_requested() {
+        assert_eq!(Ok(1024), parse_size("1KB", UnitBase::Binary));
+    }
+
+    #[test]
+    fn is_case_insensitive() {
+        assert_eq!(Ok(1_048_576), parse_size("1mib", UnitBase::Decimal));
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert_eq!(Err(ParseError::Empty), parse_size("   ", UnitBase::Decimal));
+    }
+
+    #[test]
+    fn rejects_unknown_unit() {
+        assert_eq!(
+            Err(ParseError::UnknownUnit("QB".to_string())),
+            parse_size("5QB", UnitBase::Decimal)
+        );
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        assert_eq!(
+            Err(ParseError::Overflow),
+            parse_size("100000EB", UnitBase::Decimal)
+        );
+    }
+
+    #[test]
+    fn rejects_overflow_just_past_u64_max() {
+        assert_eq!(
+            Err(ParseError::Overflow),
+            parse_size("18446744073709551616", UnitBase::Decimal)
+        );
+    }
+
+    #[test]
+    fn from_str_uses_decimal_default() {
+        let size: ParsedSize = "2.5GB".parse().unwrap();
+        assert_eq!(ParsedSize(2_500_000_000), size);
+    }
+}